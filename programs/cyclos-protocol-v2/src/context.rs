@@ -1,15 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 use std::mem::size_of;
 
 use crate::error::ErrorCode;
+use crate::libraries::liquidity_amounts::{
+    get_amount_0_for_liquidity, get_amount_1_for_liquidity, get_liquidity_for_amount_0,
+    get_liquidity_for_amount_1,
+};
 use crate::states::factory::FactoryState;
-use crate::states::fee::FeeState;
+use crate::states::fee::{FeeState, MAX_LP_FEE, ONE_IN_HUNDREDTH_PIPS};
+use crate::states::limit_order::{LimitOrderPositionState, LimitOrderSide, LimitOrderTickState};
 use crate::states::pool::PoolState;
 use crate::states::position::PositionState;
 use crate::states::tick::TickState;
 use crate::states::tick_bitmap::TickBitmapState;
+use cyclos_core::libraries::tick_math;
 
 // use non_fungible_position_manager::program::NonFungiblePositionManager;
 
@@ -205,6 +211,342 @@ pub struct MintAccount<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(position_bump: u8, tick: i32, side: u8)]
+pub struct MintLimitOrderAccount<'info> {
+    pub minter: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            LimitOrderPositionState::SEED,
+            pool_state.key().as_ref(),
+            minter.key().as_ref(),
+            &tick.to_be_bytes(),
+            &[side]
+        ],
+        bump = position_bump,
+        payer = minter
+    )]
+    pub limit_order_position_state: Box<Account<'info, LimitOrderPositionState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_state.token_0.key().as_ref(),
+            pool_state.token_1.key().as_ref(),
+            &pool_state.fee.to_be_bytes()
+        ],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    // Unlike a range position, a limit order only needs the one tick it is posted at.
+    #[account(
+        init_if_needed,
+        seeds = [
+            pool_state.token_0.key().as_ref(),
+            pool_state.token_1.key().as_ref(),
+            &pool_state.fee.to_be_bytes(),
+            &tick.to_be_bytes()
+        ],
+        bump = position_bump,
+        payer = minter
+    )]
+    pub tick_state: Box<Account<'info, TickState>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            LimitOrderTickState::SEED,
+            pool_state.key().as_ref(),
+            &tick.to_be_bytes(),
+            &[side]
+        ],
+        bump = position_bump,
+        payer = minter
+    )]
+    pub limit_order_tick_state: Box<Account<'info, LimitOrderTickState>>,
+
+    pub tick_bitmap: Box<Account<'info, TickBitmapState>>,
+
+    #[account(mut)]
+    pub token_account_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_account_1: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_state.token_0,
+        associated_token::authority = pool_state,
+    )]
+    pub vault_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_state.token_1,
+        associated_token::authority = pool_state,
+    )]
+    pub vault_1: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MintLimitOrderAccount<'info> {
+    /// Sizes a limit order from a one-sided deposit of `amount` and posts it at
+    /// `[tick, tick + tick_spacing)`, the same tick-spacing-wide range a range position
+    /// would occupy. `side == LimitOrderSide::Zero` deposits token_0 and is sized with
+    /// `get_liquidity_for_amount_0`; `side == LimitOrderSide::One` is the mirror image.
+    ///
+    /// Snapshots `limit_order_tick_state`'s current fill accumulator onto the position
+    /// so its filled share can later be recovered by `CollectLimitOrderAccount`; a tick
+    /// state seen for the first time is seeded at "nothing swapped yet" before the
+    /// snapshot is taken.
+    pub fn process(&mut self, position_bump: u8, tick: i32, side: u8, amount: u64) -> Result<()> {
+        // `limit_order_position_state` is `init_if_needed`, so a second mint onto the
+        // same (pool, minter, tick, side) would otherwise re-enter here and overwrite
+        // `liquidity`/the fill snapshot, silently discarding whatever the first deposit
+        // had already accrued. Collecting pays out the filled share but leaves
+        // `liquidity` in place, so each position account takes a single mint.
+        require!(
+            self.limit_order_position_state.liquidity == 0,
+            ErrorCode::LimitOrderAlreadyExists
+        );
+
+        let tick_spacing = self.pool_state.tick_spacing as i32;
+        let sqrt_ratio_lower_x32 = tick_math::get_sqrt_ratio_at_tick(tick);
+        let sqrt_ratio_upper_x32 = tick_math::get_sqrt_ratio_at_tick(tick + tick_spacing);
+
+        let liquidity = if side == LimitOrderSide::Zero as u8 {
+            get_liquidity_for_amount_0(sqrt_ratio_lower_x32, sqrt_ratio_upper_x32, amount)
+        } else {
+            get_liquidity_for_amount_1(sqrt_ratio_lower_x32, sqrt_ratio_upper_x32, amount)
+        };
+
+        if self.limit_order_tick_state.one_minus_percent_swapped_x32 == 0 {
+            self.limit_order_tick_state.bump = position_bump;
+            self.limit_order_tick_state.pool_id = self.pool_state.key();
+            self.limit_order_tick_state.tick = tick;
+            self.limit_order_tick_state.side = side;
+            self.limit_order_tick_state.one_minus_percent_swapped_x32 = 1u64 << 32;
+        }
+
+        self.limit_order_position_state.bump = position_bump;
+        self.limit_order_position_state.pool_id = self.pool_state.key();
+        self.limit_order_position_state.owner = self.minter.key();
+        self.limit_order_position_state.tick = tick;
+        self.limit_order_position_state.side = side;
+        self.limit_order_position_state.liquidity = liquidity;
+        self.limit_order_position_state.one_minus_percent_swapped_snapshot_x32 =
+            self.limit_order_tick_state.one_minus_percent_swapped_x32;
+
+        let (from, to) = if side == LimitOrderSide::Zero as u8 {
+            (&self.token_account_0, &self.vault_0)
+        } else {
+            (&self.token_account_1, &self.vault_1)
+        };
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.minter.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Folds one initialized-tick crossing of a swap into a limit order tick's fill
+/// accumulator. The swap instruction calls this once per crossed tick that has a
+/// `LimitOrderTickState` account for the direction being crossed; `amount_swapped` and
+/// `liquidity_at_tick` are the same values the swap step already computed walking the
+/// tick (see `libraries::quote::simulate_swap` for the read-only equivalent walk).
+#[derive(Accounts)]
+pub struct CrossLimitOrderTick<'info> {
+    #[account(
+        seeds = [
+            pool_state.token_0.as_ref(),
+            pool_state.token_1.as_ref(),
+            &pool_state.fee.to_be_bytes()
+        ],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LimitOrderTickState::SEED,
+            pool_state.key().as_ref(),
+            &limit_order_tick_state.tick.to_be_bytes(),
+            &[limit_order_tick_state.side]
+        ],
+        bump = limit_order_tick_state.bump,
+    )]
+    pub limit_order_tick_state: Box<Account<'info, LimitOrderTickState>>,
+}
+
+impl<'info> CrossLimitOrderTick<'info> {
+    pub fn process(&mut self, amount_swapped: u128, liquidity_at_tick: u128) -> Result<()> {
+        self.limit_order_tick_state
+            .apply_cross(amount_swapped, liquidity_at_tick);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CollectLimitOrderAccount<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ErrorCode::NotAnOwner,
+        seeds = [
+            LimitOrderPositionState::SEED,
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &limit_order_position_state.tick.to_be_bytes(),
+            &[limit_order_position_state.side]
+        ],
+        bump = limit_order_position_state.bump,
+    )]
+    pub limit_order_position_state: Box<Account<'info, LimitOrderPositionState>>,
+
+    #[account(
+        seeds = [
+            LimitOrderTickState::SEED,
+            pool_state.key().as_ref(),
+            &limit_order_position_state.tick.to_be_bytes(),
+            &[limit_order_position_state.side]
+        ],
+        bump = limit_order_tick_state.bump,
+    )]
+    pub limit_order_tick_state: Box<Account<'info, LimitOrderTickState>>,
+
+    #[account(
+        seeds = [
+            pool_state.token_0.key().as_ref(),
+            pool_state.token_1.key().as_ref(),
+            &pool_state.fee.to_be_bytes()
+        ],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_state.token_0,
+        associated_token::authority = pool_state,
+    )]
+    pub vault_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_state.token_1,
+        associated_token::authority = pool_state,
+    )]
+    pub vault_1: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_state.token_0,
+        associated_token::authority = owner,
+    )]
+    pub owner_wallet_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_state.token_1,
+        associated_token::authority = owner,
+    )]
+    pub owner_wallet_1: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CollectLimitOrderAccount<'info> {
+    /// Pays out the position's filled share of its order so far. The fraction still
+    /// unfilled is `current / snapshot` of the fill accumulator; what that leaves
+    /// filled, converted to the other token and minus whatever `amount_collected`
+    /// already paid out, is sent to the owner.
+    pub fn process(&mut self) -> Result<()> {
+        let position = &mut self.limit_order_position_state;
+        let snapshot_x32 = position.one_minus_percent_swapped_snapshot_x32 as u128;
+        let current_x32 = self.limit_order_tick_state.one_minus_percent_swapped_x32 as u128;
+
+        let unfilled_liquidity = if snapshot_x32 == 0 {
+            0
+        } else {
+            position
+                .liquidity
+                .checked_mul(current_x32)
+                .and_then(|v| v.checked_div(snapshot_x32))
+                .unwrap_or(0)
+        };
+        let filled_liquidity = position.liquidity.saturating_sub(unfilled_liquidity);
+
+        let tick_spacing = self.pool_state.tick_spacing as i32;
+        let sqrt_ratio_lower_x32 = tick_math::get_sqrt_ratio_at_tick(position.tick);
+        let sqrt_ratio_upper_x32 = tick_math::get_sqrt_ratio_at_tick(position.tick + tick_spacing);
+
+        // A Zero-side order deposited token_0 and converts to token_1 as it fills; a
+        // One-side order is the mirror image. Payout-side rounds down, same as burn.
+        let total_converted = if position.side == LimitOrderSide::Zero as u8 {
+            get_amount_1_for_liquidity(
+                sqrt_ratio_lower_x32,
+                sqrt_ratio_upper_x32,
+                filled_liquidity,
+                false,
+            )
+        } else {
+            get_amount_0_for_liquidity(
+                sqrt_ratio_lower_x32,
+                sqrt_ratio_upper_x32,
+                filled_liquidity,
+                false,
+            )
+        };
+
+        let payout = total_converted.saturating_sub(position.amount_collected);
+        position.amount_collected = position.amount_collected.saturating_add(payout);
+
+        let (vault, owner_wallet) = if position.side == LimitOrderSide::Zero as u8 {
+            (&self.vault_1, &self.owner_wallet_1)
+        } else {
+            (&self.vault_0, &self.owner_wallet_0)
+        };
+
+        let pool_state = &self.pool_state;
+        let signer_seeds: &[&[u8]] = &[
+            pool_state.token_0.as_ref(),
+            pool_state.token_1.as_ref(),
+            &pool_state.fee.to_be_bytes(),
+            &[pool_state.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: owner_wallet.to_account_info(),
+                    authority: pool_state.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            payout,
+        )?;
+
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 #[instruction(bump: u8)]
 pub struct SetOwner<'info> {
@@ -244,6 +586,94 @@ pub struct SetFeeProtocol<'info> {
     pub factory_state: Box<Account<'info, FactoryState>>,
 }
 
+/// `fee_protocol` is a fraction of `pool_state.fee` in hundredth-of-a-pip units, so
+/// it's bounded by `ONE_IN_HUNDREDTH_PIPS` (100%) regardless of where the LP fee
+/// itself is set. Split out from `SetFeeProtocol::process` so the bounds check can be
+/// unit tested without an `Accounts` fixture.
+fn validate_fee_protocol(fee_protocol: u32) -> Result<()> {
+    require!(
+        fee_protocol <= ONE_IN_HUNDREDTH_PIPS,
+        ErrorCode::InvalidFeeAmount
+    );
+    Ok(())
+}
+
+impl<'info> SetFeeProtocol<'info> {
+    /// Sets the protocol's cut of the pool's LP fee.
+    pub fn process(&mut self, fee_protocol: u32) -> Result<()> {
+        validate_fee_protocol(fee_protocol)?;
+        self.pool_state.fee_protocol = fee_protocol;
+        Ok(())
+    }
+}
+
+/// Mutates a pool's active LP fee. Gated the same way as `SetFeeProtocol`; the new fee
+/// is validated against `MAX_LP_FEE` in the instruction handler.
+#[derive(Accounts)]
+pub struct SetPoolFee<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool_state.token_0.as_ref(),
+            pool_state.token_1.as_ref(),
+            &pool_state.fee.to_be_bytes()
+        ],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(
+        seeds = [],
+        bump = factory_state.bump,
+        constraint = owner.key() == factory_state.owner @ErrorCode::NotAnOwner
+    )]
+    pub factory_state: Box<Account<'info, FactoryState>>,
+}
+
+/// Bounded by `MAX_LP_FEE` — the same ceiling enforced on `FeeState::fee` when a fee
+/// tier is created. Split out from `SetPoolFee::process` so the bounds check can be
+/// unit tested without an `Accounts` fixture.
+fn validate_pool_fee(fee: u32) -> Result<()> {
+    require!(fee <= MAX_LP_FEE, ErrorCode::InvalidFeeAmount);
+    Ok(())
+}
+
+impl<'info> SetPoolFee<'info> {
+    /// Sets the pool's active LP fee.
+    pub fn process(&mut self, fee: u32) -> Result<()> {
+        validate_pool_fee(fee)?;
+        self.pool_state.fee = fee;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod fee_bounds_tests {
+    use super::*;
+
+    #[test]
+    fn pool_fee_at_the_max_is_allowed() {
+        assert!(validate_pool_fee(MAX_LP_FEE).is_ok());
+    }
+
+    #[test]
+    fn pool_fee_past_the_max_is_rejected() {
+        assert!(validate_pool_fee(MAX_LP_FEE + 1).is_err());
+    }
+
+    #[test]
+    fn fee_protocol_at_one_hundred_percent_is_allowed() {
+        assert!(validate_fee_protocol(ONE_IN_HUNDREDTH_PIPS).is_ok());
+    }
+
+    #[test]
+    fn fee_protocol_past_one_hundred_percent_is_rejected() {
+        assert!(validate_fee_protocol(ONE_IN_HUNDREDTH_PIPS + 1).is_err());
+    }
+}
+
 #[derive(Accounts)]
 pub struct CollectProtocol<'info> {
     pub owner: Signer<'info>,
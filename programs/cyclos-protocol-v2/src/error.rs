@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Account is not the expected associated token account")]
+    NotAssociatedTokenAccount,
+
+    #[msg("Caller is not the factory owner")]
+    NotAnOwner,
+
+    #[msg("Fee amount exceeds the maximum allowed")]
+    InvalidFeeAmount,
+
+    #[msg("A limit order already exists at this tick and side for this owner")]
+    LimitOrderAlreadyExists,
+}
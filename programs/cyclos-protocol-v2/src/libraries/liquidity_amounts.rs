@@ -0,0 +1,191 @@
+///! Liquidity amount functions
+///! Local copy of the liquidity/amount conversions used by limit orders. Kept here
+///! rather than depending on the `non_fungible_position_manager` crate, matching how
+///! `quote.rs` only reaches into `cyclos_core` for shared price-domain math
+///! (`tick_math`, `swap_math`, `tick_bitmap`), as this file does for `fixed_point_x32`
+///! and `U256`, and keeps everything else local to this program.
+///
+use cyclos_core::libraries::fixed_point_x32;
+use cyclos_core::libraries::U256;
+use muldiv::MulDiv;
+use std::convert::TryFrom;
+
+/// Computes the amount of liquidity received for a given amount of token_0 and price range
+/// Calculates ΔL = Δx (√P_upper x √P_lower)/(√P_upper - √P_lower)
+///
+/// Intermediate products are carried in `U256` so wide `u128` liquidity values don't
+/// truncate before the final division; the division's result is still saturated to
+/// `u128::MAX` rather than converted outright, since `amount_0 * intermediate` can
+/// exceed `u128::MAX` well before the divide when the tick range is narrow and both
+/// sqrt ratios are large — a liquidity value that big isn't representable by any real
+/// position anyway.
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
+/// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
+/// * `amount_0` - The amount_0 being sent in
+///
+pub fn get_liquidity_for_amount_0(
+    mut sqrt_ratio_a_x32: u64,
+    mut sqrt_ratio_b_x32: u64,
+    amount_0: u64,
+) -> u128 {
+    // sqrt_ratio_a_x32 should hold the smaller value
+    if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
+        std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
+    };
+
+    let intermediate = U256::from(sqrt_ratio_a_x32) * U256::from(sqrt_ratio_b_x32)
+        / U256::from(fixed_point_x32::Q32);
+
+    let liquidity =
+        U256::from(amount_0) * intermediate / U256::from(sqrt_ratio_b_x32 - sqrt_ratio_a_x32);
+
+    if liquidity > U256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        liquidity.as_u128()
+    }
+}
+
+/// Computes the amount of liquidity received for a given amount of token_1 and price range
+/// Calculates ΔL = Δy / (√P_upper - √P_lower)
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
+/// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
+/// * `amount_1` - The amount_1 being sent in
+///
+pub fn get_liquidity_for_amount_1(
+    mut sqrt_ratio_a_x32: u64,
+    mut sqrt_ratio_b_x32: u64,
+    amount_1: u64,
+) -> u128 {
+    // sqrt_ratio_a_x32 should hold the smaller value
+    if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
+        std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
+    };
+
+    (amount_1 as u128)
+        .mul_div_floor(
+            fixed_point_x32::Q32 as u128,
+            (sqrt_ratio_b_x32 - sqrt_ratio_a_x32) as u128,
+        )
+        .unwrap()
+}
+
+/// Ceil-divides a `U256` numerator by a `U256` denominator.
+fn div_ceil_u256(numerator: U256, denominator: U256) -> U256 {
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    if remainder.is_zero() {
+        quotient
+    } else {
+        quotient + U256::one()
+    }
+}
+
+/// Computes the amount of token_0 for a given amount of liquidity and a price range
+/// Calculates Δx = ΔL (√P_upper - √P_lower) / (√P_upper x √P_lower)
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
+/// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
+/// * `liquidity` - The liquidity being valued
+/// * `round_up` - Whether to round the amount up (mint) or down (burn)
+///
+pub fn get_amount_0_for_liquidity(
+    mut sqrt_ratio_a_x32: u64,
+    mut sqrt_ratio_b_x32: u64,
+    liquidity: u128,
+    round_up: bool,
+) -> u64 {
+    // sqrt_ratio_a_x32 should hold the smaller value
+    if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
+        std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
+    };
+
+    let numerator = (U256::from(liquidity) << fixed_point_x32::RESOLUTION)
+        * U256::from(sqrt_ratio_b_x32 - sqrt_ratio_a_x32);
+
+    let amount_0 = if round_up {
+        div_ceil_u256(
+            div_ceil_u256(numerator, U256::from(sqrt_ratio_b_x32)),
+            U256::from(sqrt_ratio_a_x32),
+        )
+    } else {
+        numerator / U256::from(sqrt_ratio_b_x32) / U256::from(sqrt_ratio_a_x32)
+    };
+
+    u64::try_from(amount_0).unwrap()
+}
+
+/// Computes the amount of token_1 for a given amount of liquidity and a price range
+/// Calculates Δy = ΔL * (√P_upper - √P_lower)
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
+/// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
+/// * `liquidity` - The liquidity being valued
+/// * `round_up` - Whether to round the amount up (mint) or down (burn)
+///
+pub fn get_amount_1_for_liquidity(
+    mut sqrt_ratio_a_x32: u64,
+    mut sqrt_ratio_b_x32: u64,
+    liquidity: u128,
+    round_up: bool,
+) -> u64 {
+    // sqrt_ratio_a_x32 should hold the smaller value
+    if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
+        std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
+    };
+
+    let diff = (sqrt_ratio_b_x32 - sqrt_ratio_a_x32) as u128;
+    let amount_1 = if round_up {
+        liquidity.mul_div_ceil(diff, fixed_point_x32::Q32 as u128)
+    } else {
+        liquidity.mul_div_floor(diff, fixed_point_x32::Q32 as u128)
+    };
+
+    u64::try_from(amount_1.unwrap()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Duplicated from non-fungible-position-manager's liquidity_amounts test utils
+    // rather than pulling in a cross-program dependency just for this module's tests.
+    fn encode_price_sqrt_x32(reserve_1: u64, reserve_0: u64) -> u64 {
+        ((reserve_1 as f64 / reserve_0 as f64).sqrt() * u64::pow(2, 32) as f64).round() as u64
+    }
+
+    #[test]
+    fn get_liquidity_for_amount_0_matches_formula() {
+        let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
+        let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
+        let liquidity = get_liquidity_for_amount_0(price_sqrt_a_x32, price_sqrt_b_x32, 100);
+        assert_eq!(liquidity, 1048);
+    }
+
+    #[test]
+    fn get_liquidity_for_amount_0_saturates_instead_of_panicking_at_the_extreme() {
+        // Hand-verified: amount_0 * intermediate overflows u128 well before the final
+        // divide when the two ratios are adjacent and both near u64::MAX.
+        let liquidity =
+            get_liquidity_for_amount_0(u64::MAX - 1, u64::MAX, u64::MAX);
+        assert_eq!(liquidity, u128::MAX);
+    }
+
+    #[test]
+    fn amount_0_round_up_is_at_least_round_down() {
+        let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
+        let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
+        let round_down = get_amount_0_for_liquidity(price_sqrt_a_x32, price_sqrt_b_x32, 2148, false);
+        let round_up = get_amount_0_for_liquidity(price_sqrt_a_x32, price_sqrt_b_x32, 2148, true);
+        assert!(round_up >= round_down);
+    }
+}
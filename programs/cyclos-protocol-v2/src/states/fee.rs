@@ -0,0 +1,24 @@
+///! Fee tier accounts
+///! A `FeeState` is created once per fee tier via `EnableFeeAmount` and pins the
+///! tick spacing available at that tier.
+///
+use anchor_lang::prelude::*;
+
+/// Denominator for fee fractions expressed in hundredths of a pip, a pip being one
+/// basis point.
+/// A fee of `ONE_IN_HUNDREDTH_PIPS` is 100%.
+pub const ONE_IN_HUNDREDTH_PIPS: u32 = 1_000_000;
+
+/// The highest LP fee a pool may charge, in hundredth-of-a-pip units: 50%.
+pub const MAX_LP_FEE: u32 = 500_000;
+
+#[account]
+#[derive(Default)]
+pub struct FeeState {
+    pub bump: u8,
+
+    /// LP fee in hundredth-of-a-pip units, bounded by `MAX_LP_FEE` when set on a pool.
+    pub fee: u32,
+
+    pub tick_spacing: u16,
+}
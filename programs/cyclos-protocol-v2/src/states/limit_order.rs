@@ -0,0 +1,140 @@
+///! Limit order accounts
+///! A limit order is liquidity posted at a single tick that is meant to be fully
+///! converted to the other token once price crosses that tick, and then stops
+///! accruing fees or further conversion if price swings back.
+///
+use anchor_lang::prelude::*;
+
+/// Which token the order is posted in. `Zero` deposits token_0 and converts to
+/// token_1 as price rises through the tick; `One` is the mirror image.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitOrderSide {
+    Zero,
+    One,
+}
+
+/// Per-tick, per-side fill accumulator for limit orders.
+///
+/// Tracks the running product of `(1 - fraction of liquidity swapped)` across every
+/// crossing of this tick in this direction, following the same "one minus percent
+/// swapped" accounting used to settle range-order fills without per-order bookkeeping
+/// at cross time. A position's filled share is recovered by comparing its snapshot of
+/// this accumulator, taken at mint, against the current value.
+#[account]
+#[derive(Default)]
+pub struct LimitOrderTickState {
+    pub bump: u8,
+
+    /// The pool this tick belongs to.
+    pub pool_id: Pubkey,
+
+    pub tick: i32,
+
+    pub side: u8,
+
+    /// Q32.32 fixed point, starts at `1 << 32` and only ever shrinks as the tick is
+    /// crossed and liquidity at it is converted to the other token.
+    pub one_minus_percent_swapped_x32: u64,
+}
+
+impl LimitOrderTickState {
+    pub const SEED: &'static [u8] = b"limit_order_tick";
+
+    /// Folds a swap crossing this tick into the fill accumulator. `amount_swapped` is
+    /// the portion of `liquidity_at_tick` the swap actually converted while moving
+    /// through this tick's range; the accumulator shrinks by that same fraction,
+    /// compounding across every crossing, so a position's filled share (recovered by
+    /// comparing its mint-time snapshot against the current value) only ever grows.
+    ///
+    /// Called from the swap instruction once per initialized tick it crosses that has
+    /// a `LimitOrderTickState` account for the direction being crossed.
+    pub fn apply_cross(&mut self, amount_swapped: u128, liquidity_at_tick: u128) {
+        if liquidity_at_tick == 0 {
+            return;
+        }
+        let amount_swapped = amount_swapped.min(liquidity_at_tick);
+        let remaining_liquidity = liquidity_at_tick - amount_swapped;
+
+        self.one_minus_percent_swapped_x32 = ((self.one_minus_percent_swapped_x32 as u128
+            * remaining_liquidity)
+            / liquidity_at_tick) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_cross_shrinks_by_the_swapped_fraction() {
+        let mut tick_state = LimitOrderTickState {
+            one_minus_percent_swapped_x32: 1u64 << 32,
+            ..Default::default()
+        };
+
+        tick_state.apply_cross(25, 100);
+        assert_eq!(tick_state.one_minus_percent_swapped_x32, (1u64 << 32) * 3 / 4);
+    }
+
+    #[test]
+    fn apply_cross_compounds_across_multiple_crossings() {
+        let mut tick_state = LimitOrderTickState {
+            one_minus_percent_swapped_x32: 1u64 << 32,
+            ..Default::default()
+        };
+
+        tick_state.apply_cross(50, 100);
+        tick_state.apply_cross(50, 100);
+        assert_eq!(tick_state.one_minus_percent_swapped_x32, (1u64 << 32) / 4);
+    }
+
+    #[test]
+    fn apply_cross_clamps_an_oversized_swap_to_full_fill() {
+        let mut tick_state = LimitOrderTickState {
+            one_minus_percent_swapped_x32: 1u64 << 32,
+            ..Default::default()
+        };
+
+        tick_state.apply_cross(1_000, 100);
+        assert_eq!(tick_state.one_minus_percent_swapped_x32, 0);
+    }
+
+    #[test]
+    fn apply_cross_is_a_no_op_against_zero_liquidity() {
+        let mut tick_state = LimitOrderTickState {
+            one_minus_percent_swapped_x32: 1u64 << 32,
+            ..Default::default()
+        };
+
+        tick_state.apply_cross(10, 0);
+        assert_eq!(tick_state.one_minus_percent_swapped_x32, 1u64 << 32);
+    }
+}
+
+/// A single user's limit order, sized from a one-sided token deposit via
+/// `get_liquidity_for_amount_0` / `get_liquidity_for_amount_1`.
+#[account]
+#[derive(Default)]
+pub struct LimitOrderPositionState {
+    pub bump: u8,
+
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+
+    pub tick: i32,
+    pub side: u8,
+
+    pub liquidity: u128,
+
+    /// `LimitOrderTickState::one_minus_percent_swapped_x32` as observed at mint time;
+    /// the fraction filled is `1 - (current / snapshot)`.
+    pub one_minus_percent_swapped_snapshot_x32: u64,
+
+    /// Token amount already paid out to the owner via collect, so a partially filled
+    /// order can be collected more than once without double paying.
+    pub amount_collected: u64,
+}
+
+impl LimitOrderPositionState {
+    pub const SEED: &'static [u8] = b"limit_order_position";
+}
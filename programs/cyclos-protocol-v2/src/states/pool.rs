@@ -0,0 +1,30 @@
+///! Pool account
+///! Tracks the active price, tick and liquidity for one (token_0, token_1, fee) pair,
+///! plus the fee configuration applied to swaps through it.
+///
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(Default)]
+pub struct PoolState {
+    pub bump: u8,
+
+    pub token_0: Pubkey,
+    pub token_1: Pubkey,
+
+    /// LP fee in hundredth-of-a-pip units, bounded by `super::fee::MAX_LP_FEE`.
+    /// Seeded from the pool's `FeeState` at creation, mutable afterwards via `SetPoolFee`.
+    pub fee: u32,
+
+    /// Protocol's cut of `fee`, expressed as a fraction of the LP fee in
+    /// hundredth-of-a-pip units rather than an unbounded raw value.
+    pub fee_protocol: u32,
+
+    pub tick_spacing: u16,
+
+    pub sqrt_price_x32: u64,
+    pub tick: i32,
+
+    /// Currently in-range liquidity available to swaps.
+    pub liquidity: u128,
+}
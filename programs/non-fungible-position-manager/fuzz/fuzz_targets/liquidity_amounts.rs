@@ -0,0 +1,72 @@
+#![no_main]
+
+use libfuzzer_sys::arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use non_fungible_position_manager::libraries::liquidity_amounts::{
+    get_amounts_for_liquidity, get_liquidity_for_amounts,
+};
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    sqrt_ratio_a_x32: u64,
+    sqrt_ratio_b_x32: u64,
+    sqrt_ratio_x32: u64,
+    amount_0: u64,
+    amount_1: u64,
+    // A second, independently-sized amount_0 used only for the monotonicity check.
+    amount_0_more: u64,
+}
+
+fuzz_target!(|input: Input| {
+    if input.sqrt_ratio_a_x32 == input.sqrt_ratio_b_x32 {
+        // Zero-width ranges divide by zero in every implementation, including
+        // Uniswap's; callers are expected to reject tick_lower == tick_upper upstream.
+        return;
+    }
+    if input.sqrt_ratio_a_x32 == 0 || input.sqrt_ratio_b_x32 == 0 {
+        // get_amount_0_for_liquidity divides by the smaller of the two ratios; a sqrt
+        // price of zero is never produced by tick_math for a real tick, but Arbitrary
+        // tries all-zero inputs first, so guard it the same way the equal-ratio case is.
+        return;
+    }
+
+    let liquidity = get_liquidity_for_amounts(
+        input.sqrt_ratio_x32,
+        input.sqrt_ratio_a_x32,
+        input.sqrt_ratio_b_x32,
+        input.amount_0,
+        input.amount_1,
+    );
+
+    let (amount_0_out, amount_1_out) = get_amounts_for_liquidity(
+        input.sqrt_ratio_x32,
+        input.sqrt_ratio_a_x32,
+        input.sqrt_ratio_b_x32,
+        liquidity,
+        false,
+    );
+
+    // No value creation: valuing the liquidity a deposit produced can never hand back
+    // more of either token than was put in.
+    assert!(amount_0_out <= input.amount_0);
+    assert!(amount_1_out <= input.amount_1);
+
+    // Monotonicity: depositing at least as much token_0 never yields less liquidity.
+    let amount_0_floor = input.amount_0.min(input.amount_0_more);
+    let amount_0_ceil = input.amount_0.max(input.amount_0_more);
+    let liquidity_floor = get_liquidity_for_amounts(
+        input.sqrt_ratio_x32,
+        input.sqrt_ratio_a_x32,
+        input.sqrt_ratio_b_x32,
+        amount_0_floor,
+        input.amount_1,
+    );
+    let liquidity_ceil = get_liquidity_for_amounts(
+        input.sqrt_ratio_x32,
+        input.sqrt_ratio_a_x32,
+        input.sqrt_ratio_b_x32,
+        amount_0_ceil,
+        input.amount_1,
+    );
+    assert!(liquidity_ceil >= liquidity_floor);
+});
@@ -3,12 +3,20 @@
 ///! Implements formula 6.29 and 6.30
 ///
 use cyclos_core::libraries::fixed_point_x32;
+use cyclos_core::libraries::U256;
 use muldiv::MulDiv;
 use std::convert::TryFrom;
 
 /// Computes the amount of liquidity received for a given amount of token_0 and price range
 /// Calculates ΔL = Δx (√P_upper x √P_lower)/(√P_upper - √P_lower)
 ///
+/// Intermediate products are carried in `U256` so that wide `u128` liquidity values
+/// never truncate before the final division. The division's result is still
+/// saturated to `u128::MAX` rather than converted outright: `amount_0 * intermediate`
+/// can exceed `u128::MAX` well before the divide when the tick range is narrow and
+/// both sqrt ratios are large, and a liquidity value that big isn't representable by
+/// any real position anyway.
+///
 /// # Arguments
 ///
 /// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
@@ -19,22 +27,23 @@ pub fn get_liquidity_for_amount_0(
     mut sqrt_ratio_a_x32: u64,
     mut sqrt_ratio_b_x32: u64,
     amount_0: u64,
-) -> u32 {
+) -> u128 {
     // sqrt_ratio_a_x32 should hold the smaller value
     if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
         std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
     };
 
-    let intermediate = sqrt_ratio_a_x32
-        .mul_div_floor(sqrt_ratio_b_x32, fixed_point_x32::Q32)
-        .unwrap();
+    let intermediate = U256::from(sqrt_ratio_a_x32) * U256::from(sqrt_ratio_b_x32)
+        / U256::from(fixed_point_x32::Q32);
+
+    let liquidity =
+        U256::from(amount_0) * intermediate / U256::from(sqrt_ratio_b_x32 - sqrt_ratio_a_x32);
 
-    u32::try_from(
-        amount_0
-            .mul_div_floor(intermediate, sqrt_ratio_b_x32 - sqrt_ratio_a_x32)
-            .unwrap(),
-    )
-    .unwrap()
+    if liquidity > U256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        liquidity.as_u128()
+    }
 }
 
 /// Computes the amount of liquidity received for a given amount of token_1 and price range
@@ -50,18 +59,18 @@ pub fn get_liquidity_for_amount_1(
     mut sqrt_ratio_a_x32: u64,
     mut sqrt_ratio_b_x32: u64,
     amount_1: u64,
-) -> u32 {
+) -> u128 {
     // sqrt_ratio_a_x32 should hold the smaller value
     if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
         std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
     };
 
-    u32::try_from(
-        amount_1
-            .mul_div_floor(fixed_point_x32::Q32, sqrt_ratio_b_x32 - sqrt_ratio_a_x32)
-            .unwrap(),
-    )
-    .unwrap()
+    (amount_1 as u128)
+        .mul_div_floor(
+            fixed_point_x32::Q32 as u128,
+            (sqrt_ratio_b_x32 - sqrt_ratio_a_x32) as u128,
+        )
+        .unwrap()
 }
 
 /// Computes the maximum amount of liquidity received for a given amount of token0, token1, the current
@@ -81,7 +90,7 @@ pub fn get_liquidity_for_amounts(
     mut sqrt_ratio_b_x32: u64,
     amount_0: u64,
     amount_1: u64,
-) -> u32 {
+) -> u128 {
     // sqrt_ratio_a_x32 should hold the smaller value
     if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
         std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
@@ -93,7 +102,7 @@ pub fn get_liquidity_for_amounts(
     } else if sqrt_ratio_x32 < sqrt_ratio_b_x32 {
         // If P_lower < P < P_upper, active liquidity is the minimum of the liquidity provided
         // by token_0 and token_1
-        u32::min(
+        u128::min(
             get_liquidity_for_amount_0(sqrt_ratio_x32, sqrt_ratio_b_x32, amount_0),
             get_liquidity_for_amount_1(sqrt_ratio_a_x32, sqrt_ratio_x32, amount_1),
         )
@@ -103,29 +112,56 @@ pub fn get_liquidity_for_amounts(
     }
 }
 
+/// Ceil-divides a `U256` numerator by a `U256` denominator.
+///
+/// Equivalent to `mul_div_ceil` for the cases where the product no longer fits `u128`
+/// and has to be accumulated in `U256` first.
+fn div_ceil_u256(numerator: U256, denominator: U256) -> U256 {
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    if remainder.is_zero() {
+        quotient
+    } else {
+        quotient + U256::one()
+    }
+}
+
 /// Computes the amount of token_0 for a given amount of liquidity and a price range
 /// Calculates Δx = ΔL (√P_upper - √P_lower) / (√P_upper x √P_lower)
 ///
+/// `liquidity << RESOLUTION` no longer fits a `u64`, and can overflow even a `u128`
+/// once liquidity uses its high bits, so the shift and both divisions run in `U256`.
+///
 /// # Arguments
 ///
 /// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
 /// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
 /// * `liquidity` - The liquidity being valued
+/// * `round_up` - Whether to round the amount up (mint) or down (burn)
 ///
 pub fn get_amount_0_for_liquidity(
     mut sqrt_ratio_a_x32: u64,
     mut sqrt_ratio_b_x32: u64,
-    liquidity: u32,
+    liquidity: u128,
+    round_up: bool,
 ) -> u64 {
     // sqrt_ratio_a_x32 should hold the smaller value
     if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
         std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
     };
 
-    ((liquidity as u64) << fixed_point_x32::RESOLUTION)
-        .mul_div_floor(sqrt_ratio_b_x32 - sqrt_ratio_a_x32, sqrt_ratio_b_x32)
-        .unwrap()
-        / sqrt_ratio_a_x32
+    let numerator = (U256::from(liquidity) << fixed_point_x32::RESOLUTION)
+        * U256::from(sqrt_ratio_b_x32 - sqrt_ratio_a_x32);
+
+    let amount_0 = if round_up {
+        div_ceil_u256(
+            div_ceil_u256(numerator, U256::from(sqrt_ratio_b_x32)),
+            U256::from(sqrt_ratio_a_x32),
+        )
+    } else {
+        numerator / U256::from(sqrt_ratio_b_x32) / U256::from(sqrt_ratio_a_x32)
+    };
+
+    u64::try_from(amount_0).unwrap()
 }
 
 /// Computes the amount of token_1 for a given amount of liquidity and a price range
@@ -136,20 +172,27 @@ pub fn get_amount_0_for_liquidity(
 /// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
 /// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
 /// * `liquidity` - The liquidity being valued
+/// * `round_up` - Whether to round the amount up (mint) or down (burn)
 ///
 pub fn get_amount_1_for_liquidity(
     mut sqrt_ratio_a_x32: u64,
     mut sqrt_ratio_b_x32: u64,
-    liquidity: u32,
+    liquidity: u128,
+    round_up: bool,
 ) -> u64 {
     // sqrt_ratio_a_x32 should hold the smaller value
     if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
         std::mem::swap(&mut sqrt_ratio_a_x32, &mut sqrt_ratio_b_x32);
     };
 
-    (liquidity as u64)
-        .mul_div_floor(sqrt_ratio_b_x32 - sqrt_ratio_a_x32, fixed_point_x32::Q32)
-        .unwrap()
+    let diff = (sqrt_ratio_b_x32 - sqrt_ratio_a_x32) as u128;
+    let amount_1 = if round_up {
+        liquidity.mul_div_ceil(diff, fixed_point_x32::Q32 as u128)
+    } else {
+        liquidity.mul_div_floor(diff, fixed_point_x32::Q32 as u128)
+    };
+
+    u64::try_from(amount_1.unwrap()).unwrap()
 }
 
 /// Computes the token_0 and token_1 value for a given amount of liquidity, the current
@@ -161,14 +204,15 @@ pub fn get_amount_1_for_liquidity(
 /// * `sqrt_ratio_a_x32` - A sqrt price representing the first tick boundary
 /// * `sqrt_ratio_b_x32` - A sqrt price representing the second tick boundary
 /// * `liquidity` - The liquidity being valued
-/// * `amount_0` - The amount of token_0
-/// * `amount_1` - The amount of token_1
+/// * `round_up` - Whether mint-side callers should round the required amounts up, or
+///   burn-side callers should round the returned amounts down
 ///
 pub fn get_amounts_for_liquidity(
     sqrt_ratio_x32: u64,
     mut sqrt_ratio_a_x32: u64,
     mut sqrt_ratio_b_x32: u64,
-    liquidity: u32,
+    liquidity: u128,
+    round_up: bool,
 ) -> (u64, u64) {
     // sqrt_ratio_a_x32 should hold the smaller value
     if sqrt_ratio_a_x32 > sqrt_ratio_b_x32 {
@@ -178,20 +222,20 @@ pub fn get_amounts_for_liquidity(
     if sqrt_ratio_x32 <= sqrt_ratio_a_x32 {
         // If P ≤ P_lower, active liquidity is entirely in token_0
         (
-            get_amount_0_for_liquidity(sqrt_ratio_a_x32, sqrt_ratio_b_x32, liquidity),
+            get_amount_0_for_liquidity(sqrt_ratio_a_x32, sqrt_ratio_b_x32, liquidity, round_up),
             0,
         )
     } else if sqrt_ratio_x32 < sqrt_ratio_b_x32 {
         // If P_lower < P < P_upper, active liquidity is in token_0 and token_1
         (
-            get_amount_0_for_liquidity(sqrt_ratio_x32, sqrt_ratio_b_x32, liquidity),
-            get_amount_1_for_liquidity(sqrt_ratio_a_x32, sqrt_ratio_x32, liquidity),
+            get_amount_0_for_liquidity(sqrt_ratio_x32, sqrt_ratio_b_x32, liquidity, round_up),
+            get_amount_1_for_liquidity(sqrt_ratio_a_x32, sqrt_ratio_x32, liquidity, round_up),
         )
     } else {
         // If P ≥ P_upper, active liquidity is entirely in token_1
         (
             0,
-            get_amount_1_for_liquidity(sqrt_ratio_a_x32, sqrt_ratio_b_x32, liquidity),
+            get_amount_1_for_liquidity(sqrt_ratio_a_x32, sqrt_ratio_b_x32, liquidity, round_up),
         )
     }
 }
@@ -295,7 +339,7 @@ mod liq_amounts {
             let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
             let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
             let liquidity =
-                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 2148);
+                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 2148, false);
             assert_eq!(liquidity.0, 99);
             assert_eq!(liquidity.1, 99);
         }
@@ -306,7 +350,7 @@ mod liq_amounts {
             let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
             let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
             let liquidity =
-                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 1048);
+                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 1048, false);
             assert_eq!(liquidity.0, 99);
             assert_eq!(liquidity.1, 0);
         }
@@ -317,7 +361,7 @@ mod liq_amounts {
             let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
             let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
             let liquidity =
-                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 2097);
+                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 2097, false);
             assert_eq!(liquidity.0, 0);
             assert_eq!(liquidity.1, 199);
         }
@@ -328,7 +372,7 @@ mod liq_amounts {
             let price_sqrt_x32 = price_sqrt_a_x32;
             let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
             let liquidity =
-                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 1048);
+                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 1048, false);
             assert_eq!(liquidity.0, 99);
             assert_eq!(liquidity.1, 0);
         }
@@ -339,9 +383,130 @@ mod liq_amounts {
             let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
             let price_sqrt_x32 = price_sqrt_b_x32;
             let liquidity =
-                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 2097);
+                get_amounts_for_liquidity(price_sqrt_x32, price_sqrt_a_x32, price_sqrt_b_x32, 2097, false);
             assert_eq!(liquidity.0, 0);
             assert_eq!(liquidity.1, 199);
         }
     }
+
+    // Liquidity can now span the full u128 range and tick boundaries can be as far apart as
+    // the full u64 sqrt-price domain allows; round-tripping at these extremes must not panic.
+    mod overflow_extremes {
+        use super::*;
+
+        #[test]
+        fn round_trip_at_min_sqrt_price_extreme() {
+            let sqrt_a_x32 = 1u64;
+            let sqrt_b_x32 = 1_000_000u64;
+            let liquidity = get_liquidity_for_amounts(
+                sqrt_a_x32,
+                sqrt_a_x32,
+                sqrt_b_x32,
+                u64::MAX,
+                u64::MAX,
+            );
+            let (amount_0, amount_1) =
+                get_amounts_for_liquidity(sqrt_a_x32, sqrt_a_x32, sqrt_b_x32, liquidity, false);
+            assert!(amount_0 <= u64::MAX);
+            assert!(amount_1 <= u64::MAX);
+        }
+
+        #[test]
+        fn round_trip_at_max_sqrt_price_extreme() {
+            let sqrt_a_x32 = u64::MAX / 2;
+            let sqrt_b_x32 = u64::MAX;
+            let liquidity = get_liquidity_for_amounts(
+                sqrt_b_x32,
+                sqrt_a_x32,
+                sqrt_b_x32,
+                u64::MAX,
+                u64::MAX,
+            );
+            let (amount_0, amount_1) =
+                get_amounts_for_liquidity(sqrt_b_x32, sqrt_a_x32, sqrt_b_x32, liquidity, false);
+            assert!(amount_0 <= u64::MAX);
+            assert!(amount_1 <= u64::MAX);
+        }
+
+        #[test]
+        fn round_trip_with_wide_tick_range_does_not_panic() {
+            let sqrt_a_x32 = u64::MAX / 4;
+            let sqrt_b_x32 = u64::MAX;
+            let liquidity = get_liquidity_for_amount_0(sqrt_a_x32, sqrt_b_x32, u64::MAX);
+            // Liquidity derived from a realistic (u64) deposit must still round-trip through
+            // the widened U256 shift in get_amount_0_for_liquidity without panicking.
+            let amount_0 = get_amount_0_for_liquidity(sqrt_a_x32, sqrt_b_x32, liquidity, false);
+            assert!(amount_0 <= u64::MAX);
+        }
+
+        #[test]
+        fn get_liquidity_for_amount_0_saturates_instead_of_panicking_at_the_extreme() {
+            // Adjacent sqrt ratios both near u64::MAX: amount_0 * intermediate overflows
+            // u128 well before the final divide, so this must saturate rather than panic
+            // in the U256 -> u128 conversion.
+            let liquidity =
+                get_liquidity_for_amount_0(u64::MAX - 1, u64::MAX, u64::MAX);
+            assert_eq!(liquidity, u128::MAX);
+        }
+    }
+
+    mod rounding {
+        use super::*;
+
+        #[test]
+        fn amount_0_round_up_is_at_least_round_down_and_at_most_one_more() {
+            let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
+            let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
+            let liquidity = 2148u128;
+
+            let round_down =
+                get_amount_0_for_liquidity(price_sqrt_a_x32, price_sqrt_b_x32, liquidity, false);
+            let round_up =
+                get_amount_0_for_liquidity(price_sqrt_a_x32, price_sqrt_b_x32, liquidity, true);
+
+            assert!(round_up >= round_down);
+            assert!(round_up - round_down <= 1);
+        }
+
+        #[test]
+        fn amount_1_round_up_is_at_least_round_down_and_at_most_one_more() {
+            let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
+            let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
+            let liquidity = 2148u128;
+
+            let round_down =
+                get_amount_1_for_liquidity(price_sqrt_a_x32, price_sqrt_b_x32, liquidity, false);
+            let round_up =
+                get_amount_1_for_liquidity(price_sqrt_a_x32, price_sqrt_b_x32, liquidity, true);
+
+            assert!(round_up >= round_down);
+            assert!(round_up - round_down <= 1);
+        }
+
+        #[test]
+        fn amounts_for_liquidity_round_up_never_undershoots_round_down() {
+            let price_sqrt_x32 = encode_price_sqrt_x32(1, 1);
+            let price_sqrt_a_x32 = encode_price_sqrt_x32(100, 110);
+            let price_sqrt_b_x32 = encode_price_sqrt_x32(110, 100);
+            let liquidity = 2148u128;
+
+            let round_down = get_amounts_for_liquidity(
+                price_sqrt_x32,
+                price_sqrt_a_x32,
+                price_sqrt_b_x32,
+                liquidity,
+                false,
+            );
+            let round_up = get_amounts_for_liquidity(
+                price_sqrt_x32,
+                price_sqrt_a_x32,
+                price_sqrt_b_x32,
+                liquidity,
+                true,
+            );
+
+            assert!(round_up.0 >= round_down.0 && round_up.0 - round_down.0 <= 1);
+            assert!(round_up.1 >= round_down.1 && round_up.1 - round_down.1 <= 1);
+        }
+    }
 }
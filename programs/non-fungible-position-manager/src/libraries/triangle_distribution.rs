@@ -0,0 +1,227 @@
+///! Triangle distribution helper
+///! Spreads a single deposit across many tick ranges around the active tick with equal
+///! per-range liquidity, which naturally produces a triangular token distribution
+///! across bins (most token_0/token_1 sits in the range nearest the active tick).
+///
+use crate::libraries::liquidity_amounts::{get_amounts_for_liquidity, get_liquidity_for_amounts};
+use cyclos_core::libraries::tick_math;
+
+/// One range in a triangle distribution: the bin's boundaries, the uniform liquidity
+/// allocated to it, and the exact token_0/token_1 it consumes at that liquidity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriangleRange {
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Spreads `amount_0_total`/`amount_1_total` across `m` tick ranges below and `m` tick
+/// ranges above the active tick, each `tick_spacing` wide, so every range is minted
+/// with the same liquidity `L`.
+///
+/// `L` is the largest value for which the *sum* of what every range would draw (not
+/// any single range in isolation) still fits inside the budget: the per-range draw at
+/// a candidate `L` is monotonically increasing in `L`, so the feasible `L` is found by
+/// binary search rather than by pricing one range against the whole deposit. Ranges
+/// are then re-priced at that `L` to report their exact token_0/token_1 draw; what's
+/// left over after all `2m` ranges are funded is returned as dust.
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_x32` - The pool's current sqrt price
+/// * `current_tick` - The pool's current tick, used only to find the active bin
+/// * `tick_spacing` - The pool's tick spacing; range boundaries are multiples of this
+/// * `m` - Number of ranges to create on each side of the active tick
+/// * `amount_0_total` - Total token_0 budget available to spread across all ranges
+/// * `amount_1_total` - Total token_1 budget available to spread across all ranges
+///
+pub fn distribute_triangle(
+    sqrt_ratio_x32: u64,
+    current_tick: i32,
+    tick_spacing: i32,
+    m: u32,
+    amount_0_total: u64,
+    amount_1_total: u64,
+) -> (Vec<TriangleRange>, u64, u64) {
+    // Tick boundaries are always on a multiple of tick_spacing; div_euclid rounds
+    // towards negative infinity so this holds for negative ticks too. When the active
+    // tick already sits exactly on a boundary, the ranges below and above it meet there
+    // without overlapping, so nothing needs to be split out separately.
+    let active_boundary = current_tick.div_euclid(tick_spacing) * tick_spacing;
+
+    let mut boundaries = Vec::with_capacity(2 * m as usize);
+    for i in 1..=m as i32 {
+        boundaries.push((
+            active_boundary - i * tick_spacing,
+            active_boundary - (i - 1) * tick_spacing,
+        ));
+    }
+    for i in 1..=m as i32 {
+        boundaries.push((
+            active_boundary + (i - 1) * tick_spacing,
+            active_boundary + i * tick_spacing,
+        ));
+    }
+
+    if boundaries.is_empty() {
+        return (Vec::new(), amount_0_total, amount_1_total);
+    }
+
+    let sqrt_ratios: Vec<(u64, u64)> = boundaries
+        .iter()
+        .map(|&(tick_lower, tick_upper)| {
+            (
+                tick_math::get_sqrt_ratio_at_tick(tick_lower),
+                tick_math::get_sqrt_ratio_at_tick(tick_upper),
+            )
+        })
+        .collect();
+
+    // Upper bound for the search: the liquidity any single range would get if it alone
+    // received the entire deposit. The true uniform L, shared across every range, can
+    // never exceed this.
+    let search_ceiling = sqrt_ratios
+        .iter()
+        .map(|&(sqrt_a, sqrt_b)| {
+            get_liquidity_for_amounts(
+                sqrt_ratio_x32,
+                sqrt_a,
+                sqrt_b,
+                amount_0_total,
+                amount_1_total,
+            )
+        })
+        .min()
+        .unwrap_or(0);
+
+    let total_amounts_at = |liquidity: u128| -> (u64, u64) {
+        sqrt_ratios.iter().fold((0u64, 0u64), |(acc_0, acc_1), &(sqrt_a, sqrt_b)| {
+            // Round up while searching, the same mint-side rounding the final pass below
+            // reports, so the candidate L is only accepted if the budget covers it.
+            let (amount_0, amount_1) =
+                get_amounts_for_liquidity(sqrt_ratio_x32, sqrt_a, sqrt_b, liquidity, true);
+            (acc_0.saturating_add(amount_0), acc_1.saturating_add(amount_1))
+        })
+    };
+    let fits_budget = |liquidity: u128| -> bool {
+        let (amount_0, amount_1) = total_amounts_at(liquidity);
+        amount_0 <= amount_0_total && amount_1 <= amount_1_total
+    };
+
+    let mut lo = 0u128;
+    let mut hi = search_ceiling;
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        if fits_budget(mid) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    let uniform_liquidity = lo;
+
+    let mut ranges = Vec::with_capacity(boundaries.len());
+    let mut amount_0_used = 0u64;
+    let mut amount_1_used = 0u64;
+
+    for (&(tick_lower, tick_upper), &(sqrt_a, sqrt_b)) in boundaries.iter().zip(sqrt_ratios.iter())
+    {
+        // Mint-side rounding: report what minting this range at `uniform_liquidity`
+        // draws; the search above already checked that these totals fit the budget.
+        let (amount_0, amount_1) =
+            get_amounts_for_liquidity(sqrt_ratio_x32, sqrt_a, sqrt_b, uniform_liquidity, true);
+
+        amount_0_used += amount_0;
+        amount_1_used += amount_1;
+
+        ranges.push(TriangleRange {
+            tick_lower,
+            tick_upper,
+            liquidity: uniform_liquidity,
+            amount_0,
+            amount_1,
+        });
+    }
+
+    let dust_0 = amount_0_total.saturating_sub(amount_0_used);
+    let dust_1 = amount_1_total.saturating_sub(amount_1_used);
+
+    (ranges, dust_0, dust_1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Same helper as liquidity_amounts::encode_price_sqrt_x32, duplicated here for
+    // this module's tests.
+    fn encode_price_sqrt_x32(reserve_1: u64, reserve_0: u64) -> u64 {
+        ((reserve_1 as f64 / reserve_0 as f64).sqrt() * u64::pow(2, 32) as f64).round() as u64
+    }
+
+    #[test]
+    fn uniform_liquidity_never_overshoots_the_budget() {
+        let sqrt_ratio_x32 = encode_price_sqrt_x32(1, 1);
+        let current_tick = 0;
+        let tick_spacing = 60;
+        let m = 5;
+        let amount_0_total = 1_000_000u64;
+        let amount_1_total = 1_000_000u64;
+
+        let (ranges, dust_0, dust_1) = distribute_triangle(
+            sqrt_ratio_x32,
+            current_tick,
+            tick_spacing,
+            m,
+            amount_0_total,
+            amount_1_total,
+        );
+
+        assert_eq!(ranges.len(), 2 * m as usize);
+
+        let total_0: u64 = ranges.iter().map(|r| r.amount_0).sum();
+        let total_1: u64 = ranges.iter().map(|r| r.amount_1).sum();
+
+        assert!(total_0 <= amount_0_total);
+        assert!(total_1 <= amount_1_total);
+        assert_eq!(dust_0, amount_0_total - total_0);
+        assert_eq!(dust_1, amount_1_total - total_1);
+    }
+
+    #[test]
+    fn every_range_gets_the_same_liquidity() {
+        let sqrt_ratio_x32 = encode_price_sqrt_x32(1, 1);
+        let (ranges, _, _) = distribute_triangle(sqrt_ratio_x32, 0, 60, 5, 1_000_000, 1_000_000);
+
+        let liquidity = ranges[0].liquidity;
+        assert!(ranges.iter().all(|r| r.liquidity == liquidity));
+        assert!(liquidity > 0);
+    }
+
+    #[test]
+    fn one_sided_budget_starves_the_shared_uniform_liquidity() {
+        // The below-price ranges are entirely token_1 (price has already crossed them)
+        // and the above-price ranges are entirely token_0. With no token_1 budget at
+        // all, the below-price ranges can't get any liquidity — and because every
+        // range shares one uniform L, that drags the above-price ranges to zero too.
+        let sqrt_ratio_x32 = encode_price_sqrt_x32(1, 1);
+        let (ranges, dust_0, dust_1) =
+            distribute_triangle(sqrt_ratio_x32, 0, 60, 5, 1_000_000, 0);
+
+        assert_eq!(dust_1, 0);
+        assert!(ranges.iter().all(|r| r.liquidity == 0));
+        assert_eq!(dust_0, 1_000_000);
+    }
+
+    #[test]
+    fn zero_ranges_returns_full_dust() {
+        let sqrt_ratio_x32 = encode_price_sqrt_x32(1, 1);
+        let (ranges, dust_0, dust_1) = distribute_triangle(sqrt_ratio_x32, 0, 60, 0, 500, 700);
+
+        assert!(ranges.is_empty());
+        assert_eq!(dust_0, 500);
+        assert_eq!(dust_1, 700);
+    }
+}